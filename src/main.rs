@@ -1,6 +1,7 @@
 use std::env;
 use std::fs::File;
-use std::io::{self, Read, Write};
+use std::io;
+use std::io::{Read, Write};
 use std::ops::Index;
 use std::ops::IndexMut;
 
@@ -18,20 +19,150 @@ use std::ops::IndexMut;
 enum BfError {
     MismatchedBraces,
     Segfault,
+    IoError,
 }
 
 type BfStateResult = Result<(), BfError>;
 
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Endian {
+    Little,
+    Big,
+}
+
+// Fills `buf` from `input`, looping over short reads. Returns whether any
+// bytes were read at all, so the caller can tell a clean EOF (no bytes,
+// cell left as the default) from a partial read at the tail of the stream
+// (zero-padded, same as a clean EOF would be for the missing bytes).
+fn fill_or_zero<R: Read>(input: &mut R, buf: &mut [u8]) -> Result<bool, BfError> {
+    let mut total = 0;
+    while total < buf.len() {
+        match input.read(&mut buf[total..]) {
+            Ok(0) => break,
+            Ok(n) => total += n,
+            Err(_) => return Err(BfError::IoError),
+        }
+    }
+    return Ok(total > 0);
+}
+
+// Abstracts the tape's cell width (u8/u16/u32) so `GrowableVect` and
+// `BfState` don't need to hardcode a byte size. `,`/`.` move `byte_width()`
+// bytes at a time, using the little/big-endian byte order carried by the
+// calling `BfState`, mirroring the byteorder crate's ReadBytesExt/WriteBytesExt
+// split without requiring the dependency.
+trait Cell: Copy + Default + PartialEq {
+    #[allow(dead_code)]
+    fn byte_width() -> usize;
+    fn cell_add(self, delta: u8) -> Self;
+    fn cell_sub(self, delta: u8) -> Self;
+    fn read_cell<R: Read>(input: &mut R, endian: Endian) -> Result<Self, BfError>;
+    fn write_cell<W: Write>(self, output: &mut W, endian: Endian) -> BfStateResult;
+}
+
+impl Cell for u8 {
+    fn byte_width() -> usize {
+        1
+    }
+
+    fn cell_add(self, delta: u8) -> Self {
+        self.wrapping_add(delta)
+    }
+
+    fn cell_sub(self, delta: u8) -> Self {
+        self.wrapping_sub(delta)
+    }
+
+    fn read_cell<R: Read>(input: &mut R, _endian: Endian) -> Result<Self, BfError> {
+        let mut buf = [0u8; 1];
+        if fill_or_zero(input, &mut buf)? {
+            Ok(buf[0])
+        } else {
+            Ok(0)
+        }
+    }
+
+    fn write_cell<W: Write>(self, output: &mut W, _endian: Endian) -> BfStateResult {
+        output.write_all(&[self]).map_err(|_| BfError::IoError)
+    }
+}
+
+impl Cell for u16 {
+    fn byte_width() -> usize {
+        2
+    }
+
+    fn cell_add(self, delta: u8) -> Self {
+        self.wrapping_add(delta as u16)
+    }
+
+    fn cell_sub(self, delta: u8) -> Self {
+        self.wrapping_sub(delta as u16)
+    }
+
+    fn read_cell<R: Read>(input: &mut R, endian: Endian) -> Result<Self, BfError> {
+        let mut buf = [0u8; 2];
+        if !fill_or_zero(input, &mut buf)? {
+            return Ok(0);
+        }
+        Ok(match endian {
+            Endian::Little => u16::from_le_bytes(buf),
+            Endian::Big => u16::from_be_bytes(buf),
+        })
+    }
+
+    fn write_cell<W: Write>(self, output: &mut W, endian: Endian) -> BfStateResult {
+        let bytes = match endian {
+            Endian::Little => self.to_le_bytes(),
+            Endian::Big => self.to_be_bytes(),
+        };
+        output.write_all(&bytes).map_err(|_| BfError::IoError)
+    }
+}
+
+impl Cell for u32 {
+    fn byte_width() -> usize {
+        4
+    }
+
+    fn cell_add(self, delta: u8) -> Self {
+        self.wrapping_add(delta as u32)
+    }
+
+    fn cell_sub(self, delta: u8) -> Self {
+        self.wrapping_sub(delta as u32)
+    }
+
+    fn read_cell<R: Read>(input: &mut R, endian: Endian) -> Result<Self, BfError> {
+        let mut buf = [0u8; 4];
+        if !fill_or_zero(input, &mut buf)? {
+            return Ok(0);
+        }
+        Ok(match endian {
+            Endian::Little => u32::from_le_bytes(buf),
+            Endian::Big => u32::from_be_bytes(buf),
+        })
+    }
+
+    fn write_cell<W: Write>(self, output: &mut W, endian: Endian) -> BfStateResult {
+        let bytes = match endian {
+            Endian::Little => self.to_le_bytes(),
+            Endian::Big => self.to_be_bytes(),
+        };
+        output.write_all(&bytes).map_err(|_| BfError::IoError)
+    }
+}
+
 #[derive(Debug)]
-struct GrowableVect {
-    arr: Vec<u8>,
-    default_value: u8,
+struct GrowableVect<T> {
+    arr: Vec<T>,
+    default_value: T,
 }
 
-impl Index<usize> for GrowableVect {
-    type Output = u8;
+impl<T: Copy> Index<usize> for GrowableVect<T> {
+    type Output = T;
 
-    fn index(&self, index: usize) -> &u8 {
+    fn index(&self, index: usize) -> &T {
         if index >= self.arr.len() {
             return &self.default_value;
         }
@@ -39,8 +170,8 @@ impl Index<usize> for GrowableVect {
     }
 }
 
-impl IndexMut<usize> for GrowableVect {
-    fn index_mut<'a>(&'a mut self, index: usize) -> &'a mut u8 {
+impl<T: Copy> IndexMut<usize> for GrowableVect<T> {
+    fn index_mut<'a>(&'a mut self, index: usize) -> &'a mut T {
         if index >= self.arr.len() {
             self.arr.resize(index + 1, self.default_value);
         }
@@ -48,30 +179,37 @@ impl IndexMut<usize> for GrowableVect {
     }
 }
 
-impl GrowableVect {
-    fn new() -> GrowableVect {
-        let arr: Vec<u8> = Vec::new();
+impl<T: Copy + Default> GrowableVect<T> {
+    fn new() -> GrowableVect<T> {
+        let arr: Vec<T> = Vec::new();
         GrowableVect {
             arr: arr,
-            default_value: 0,
+            default_value: T::default(),
         }
     }
 }
 
-struct BfState {
-    memory: GrowableVect,
+struct BfState<T: Cell> {
+    memory: GrowableVect<T>,
     pointer: usize,
+    endian: Endian,
 }
 
-impl BfState {
-    fn new() -> BfState {
+impl<T: Cell> BfState<T> {
+    #[allow(dead_code)]
+    fn new() -> BfState<T> {
+        BfState::with_endian(Endian::Little)
+    }
+
+    fn with_endian(endian: Endian) -> BfState<T> {
         BfState {
             memory: GrowableVect::new(),
             pointer: 0,
+            endian: endian,
         }
     }
 
-    fn curr(&self) -> u8 {
+    fn curr(&self) -> T {
         return self.memory[self.pointer];
     }
 
@@ -91,129 +229,218 @@ impl BfState {
     }
     */
 
-    fn set_curr(&mut self, value: u8) {
+    fn set_curr(&mut self, value: T) {
         self.memory[self.pointer] = value;
     }
 
-    fn inc(&mut self) {
-        let (result, _) = self.curr().overflowing_add(1);
+    fn add(&mut self, count: u8) {
+        let result = self.curr().cell_add(count);
         self.set_curr(result);
     }
 
-    fn dec(&mut self) {
-        let (result, _) = self.curr().overflowing_sub(1);
+    fn sub(&mut self, count: u8) {
+        let result = self.curr().cell_sub(count);
         self.set_curr(result);
     }
 
-    fn left(&mut self) -> BfStateResult {
-        if self.pointer == 0 {
+    fn move_left(&mut self, count: usize) -> BfStateResult {
+        if count > self.pointer {
             return Err(BfError::Segfault);
         }
-        self.pointer -= 1;
+        self.pointer -= count;
         Ok(())
     }
 
+    fn move_right(&mut self, count: usize) {
+        self.pointer += count;
+    }
+
+    #[allow(dead_code)]
+    fn inc(&mut self) {
+        self.add(1);
+    }
+
+    #[allow(dead_code)]
+    fn dec(&mut self) {
+        self.sub(1);
+    }
+
+    #[allow(dead_code)]
+    fn left(&mut self) -> BfStateResult {
+        self.move_left(1)
+    }
+
+    #[allow(dead_code)]
     fn right(&mut self) {
-        self.pointer += 1;
+        self.move_right(1);
     }
-}
 
-fn read() -> u8 {
-    return io::stdin().bytes().next().expect("reached end of stdin").expect("failed to extract bytes");
-}
+    // On EOF, `,` leaves the current cell set to 0 rather than erroring, matching
+    // the common Brainfuck convention for exhausted input. A short read at the
+    // tail of the stream is treated the same way, zero-padding the missing bytes.
+    fn read<R: Read>(&mut self, input: &mut R) -> BfStateResult {
+        let value = T::read_cell(input, self.endian)?;
+        self.set_curr(value);
+        Ok(())
+    }
 
-fn write(c: u8) {
-    print!("{}", c as char);
-    io::stdout().flush().expect("stdout.flush() failed");
+    fn write<W: Write>(&self, output: &mut W) -> BfStateResult {
+        self.curr().write_cell(output, self.endian)
+    }
 }
 
-fn build_pc_pairs(program: &str, pc_pairs: &mut Vec<(usize, usize)>) -> BfStateResult {
-    let mut pc_stack: Vec<usize> = Vec::new();
+#[derive(Debug, Clone, Copy)]
+enum Instr {
+    Add(u8),
+    Sub(u8),
+    Left(usize),
+    Right(usize),
+    Read,
+    Write,
+    // carries the index of the matching `]`
+    JumpIfZero(usize),
+    // carries the index of the matching `[`
+    JumpIfNonZero(usize),
+}
 
-    for (index, sym) in program.char_indices() {
-        if sym == '[' {
-            pc_stack.push(index);
-        }
-        if sym == ']' {
-            let result = match pc_stack.pop() {
-                None => Err(BfError::MismatchedBraces),
-                Some(left_pc) => Ok(pc_pairs.push((left_pc, index))),
-            };
-            if result.is_err() {
-                return result;
-            }
+// Caps a folded run at 255 rather than wrapping the count itself: for u8
+// cells that's equivalent mod 256, but for wider cells a run of more than
+// 255 identical ops must still add up to its true total, so a run longer
+// than 255 spills into a second instruction instead of losing the excess.
+fn fold_add(instrs: &mut Vec<Instr>) {
+    if let Some(Instr::Add(count)) = instrs.last_mut() {
+        if *count < u8::MAX {
+            *count += 1;
+            return;
         }
     }
-    if !pc_stack.is_empty() {
-        return Err(BfError::MismatchedBraces);
+    instrs.push(Instr::Add(1));
+}
+
+fn fold_sub(instrs: &mut Vec<Instr>) {
+    if let Some(Instr::Sub(count)) = instrs.last_mut() {
+        if *count < u8::MAX {
+            *count += 1;
+            return;
+        }
     }
+    instrs.push(Instr::Sub(1));
+}
 
-    return Ok(());
+fn fold_left(instrs: &mut Vec<Instr>) {
+    if let Some(Instr::Left(count)) = instrs.last_mut() {
+        *count += 1;
+        return;
+    }
+    instrs.push(Instr::Left(1));
 }
 
-fn match_left_pc(pairs: &Vec<(usize, usize)>, left_pc: usize) -> Option<usize> {
-    for pair in pairs {
-        if pair.0 == left_pc {
-            return Some(pair.1);
-        }
+fn fold_right(instrs: &mut Vec<Instr>) {
+    if let Some(Instr::Right(count)) = instrs.last_mut() {
+        *count += 1;
+        return;
     }
-    return None;
+    instrs.push(Instr::Right(1));
 }
 
-fn match_right_pc(pairs: &Vec<(usize, usize)>, right_pc: usize) -> Option<usize> {
-    for pair in pairs {
-        if pair.1 == right_pc {
-            return Some(pair.0);
+fn compile(program: &str) -> Result<Vec<Instr>, BfError> {
+    let mut instrs: Vec<Instr> = Vec::new();
+    let mut jump_stack: Vec<usize> = Vec::new();
+
+    for sym in program.chars() {
+        match sym {
+            '+' => fold_add(&mut instrs),
+            '-' => fold_sub(&mut instrs),
+            '<' => fold_left(&mut instrs),
+            '>' => fold_right(&mut instrs),
+            ',' => instrs.push(Instr::Read),
+            '.' => instrs.push(Instr::Write),
+            '[' => {
+                jump_stack.push(instrs.len());
+                instrs.push(Instr::JumpIfZero(0));
+            },
+            ']' => {
+                let open = match jump_stack.pop() {
+                    None => return Err(BfError::MismatchedBraces),
+                    Some(open) => open,
+                };
+                let close = instrs.len();
+                instrs[open] = Instr::JumpIfZero(close);
+                instrs.push(Instr::JumpIfNonZero(open));
+            },
+            _ => {},
         }
     }
-    return None;
+    if !jump_stack.is_empty() {
+        return Err(BfError::MismatchedBraces);
+    }
+
+    return Ok(instrs);
 }
 
-fn run(program: &str, state: &mut BfState) -> BfStateResult {
-    let mut pc_pairs: Vec<(usize, usize)> = Vec::new();
-    let mut result = build_pc_pairs(program, &mut pc_pairs);
-    if result.is_err() {
-        return result;
-    }
+fn run<T: Cell, R: Read, W: Write>(program: &str, state: &mut BfState<T>, input: &mut R, output: &mut W) -> BfStateResult {
+    let instrs = compile(program)?;
 
     let mut pc = 0;
-    let symbols: Vec<char> = program.chars().collect();
-    while pc < symbols.len() {
-        let sym = symbols[pc];
-        result = match sym {
-            '+' => Ok(state.inc()),
-            '-' => Ok(state.dec()),
-            '>' => Ok(state.right()),
-            '<' => state.left(),
-            ',' => Ok(state.set_curr(read())),
-            '.' => Ok(write(state.curr())),
-            '[' => {
-                if state.curr() == 0 {
-                    pc = match_left_pc(&pc_pairs, pc).unwrap();
+    while pc < instrs.len() {
+        let mut result = Ok(());
+        let mut next_pc = pc + 1;
+        match instrs[pc] {
+            Instr::Add(count) => state.add(count),
+            Instr::Sub(count) => state.sub(count),
+            Instr::Right(count) => state.move_right(count),
+            Instr::Left(count) => result = state.move_left(count),
+            Instr::Read => result = state.read(input),
+            Instr::Write => result = state.write(output),
+            Instr::JumpIfZero(target) => {
+                if state.curr() == T::default() {
+                    next_pc = target + 1;
                 }
-                Ok(())
             },
-            ']' => {
-                pc = match_right_pc(&pc_pairs, pc).unwrap() - 1;
-                Ok(())
+            Instr::JumpIfNonZero(target) => {
+                if state.curr() != T::default() {
+                    next_pc = target;
+                }
             },
-            _ => Ok(()),
-        };
+        }
         if result.is_err() {
             return result;
-        } 
-        pc = pc + 1;
+        }
+        pc = next_pc;
     }
-    return result;
+    return Ok(());
 }
 
 fn main() {
+    let mut cell_width: u8 = 8;
+    let mut endian = Endian::Little;
+    let mut files: Vec<String> = Vec::new();
+
     for arg in env::args().skip(1) {
+        if let Some(width) = arg.strip_prefix("--cell-width=") {
+            cell_width = width.parse().expect("--cell-width must be 8, 16, or 32");
+        } else if arg == "--big-endian" {
+            endian = Endian::Big;
+        } else if arg == "--little-endian" {
+            endian = Endian::Little;
+        } else {
+            files.push(arg);
+        }
+    }
+
+    for path in files {
         let mut buf = String::new();
-        let mut file = File::open(arg).expect("couldn't open that file bro");
+        let mut file = File::open(path).expect("couldn't open that file bro");
         file.read_to_string(&mut buf).expect("couldn't read from file");
-        let mut state = BfState::new();
-        run(buf.trim(), &mut state).expect("error running bf program!");
+        let program = buf.trim();
+
+        let result = match cell_width {
+            8 => run(program, &mut BfState::<u8>::with_endian(endian), &mut io::stdin(), &mut io::stdout()),
+            16 => run(program, &mut BfState::<u16>::with_endian(endian), &mut io::stdin(), &mut io::stdout()),
+            32 => run(program, &mut BfState::<u32>::with_endian(endian), &mut io::stdin(), &mut io::stdout()),
+            _ => panic!("--cell-width must be 8, 16, or 32"),
+        };
+        result.expect("error running bf program!");
         println!("");
     }
 }
@@ -221,18 +448,25 @@ fn main() {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::io::Cursor;
+
+    fn run_str(program: &str, state: &mut BfState<u8>) -> BfStateResult {
+        let mut input = Cursor::new(Vec::new());
+        let mut output = Vec::new();
+        run(program, state, &mut input, &mut output)
+    }
 
     // GrowableVect
     #[test]
     fn can_index_growable_vects() {
-        let mut vect = GrowableVect::new();
+        let mut vect: GrowableVect<u8> = GrowableVect::new();
         vect[0] = 33;
         assert_eq!(vect[0], 33);
     }
 
     #[test]
     fn growable_vects_have_sensible_index_defaults() {
-        let vect = GrowableVect::new();
+        let vect: GrowableVect<u8> = GrowableVect::new();
         assert_eq!(vect[0], 0);
         assert_eq!(vect[33], 0);
     }
@@ -240,7 +474,7 @@ mod tests {
     // BfState
     #[test]
     fn test_inc() {
-        let mut state = BfState::new();
+        let mut state = BfState::<u8>::new();
         state.inc();
         assert_eq!(state.curr(), 1);
         state.inc();
@@ -249,7 +483,7 @@ mod tests {
 
     #[test]
     fn test_dec() {
-        let mut state = BfState::new();
+        let mut state = BfState::<u8>::new();
         state.memory[state.pointer] = 200;
         state.dec();
         assert_eq!(state.curr(), 199);
@@ -259,7 +493,7 @@ mod tests {
 
     #[test]
     fn test_curr() {
-        let mut state = BfState::new();
+        let mut state = BfState::<u8>::new();
         assert_eq!(state.curr(), 0);
 
         state.pointer = 13;
@@ -271,7 +505,7 @@ mod tests {
 
     #[test]
     fn increment_overflow_test() {
-        let mut state = BfState::new();
+        let mut state = BfState::<u8>::new();
         state.memory[0] = 255;
         state.inc();
         assert_eq!(state.curr(), 0);
@@ -279,7 +513,7 @@ mod tests {
 
     #[test]
     fn decrement_underflow_test() {
-        let mut state = BfState::new();
+        let mut state = BfState::<u8>::new();
         state.memory[0] = 0;
         state.dec();
         assert_eq!(state.curr(), 255);
@@ -287,14 +521,14 @@ mod tests {
 
     #[test]
     fn set_curr_test() {
-        let mut state = BfState::new();
+        let mut state = BfState::<u8>::new();
         state.set_curr(10);
         assert_eq!(state.curr(), 10);
     }
 
     #[test]
     fn right() {
-        let mut state = BfState::new();
+        let mut state = BfState::<u8>::new();
         assert_eq!(state.pointer, 0);
         state.right();
         assert_eq!(state.pointer, 1);
@@ -304,7 +538,7 @@ mod tests {
 
     #[test]
     fn run_left() {
-        let mut state = BfState::new();
+        let mut state = BfState::<u8>::new();
         state.pointer = 200;
         assert!(state.left().is_ok());
         assert_eq!(state.pointer, 199);
@@ -314,84 +548,191 @@ mod tests {
 
     #[test]
     fn run_left_handle_segfault() {
-        let mut state = BfState::new();
-        let result = run("<", &mut state);
+        let mut state = BfState::<u8>::new();
+        let result = run_str("<", &mut state);
         assert!(result.is_err());
         assert_eq!(result.err(), Some(BfError::Segfault));
     }
 
     #[test]
     fn run_propagates_segfault_err() {
-        let result = run("<", &mut BfState::new());
+        let result = run_str("<", &mut BfState::<u8>::new());
         assert!(result.is_err());
         assert_eq!(result.err(), Some(BfError::Segfault));
     }
 
     #[test]
     fn run_ok_on_empty_program() {
-        assert!(run("", &mut BfState::new()).is_ok());
+        assert!(run_str("", &mut BfState::<u8>::new()).is_ok());
     }
 
     #[test]
     fn run_empty_loop() {
-        let mut state = BfState::new();
-        assert!(run("[]", &mut state).is_ok());
+        let mut state = BfState::<u8>::new();
+        assert!(run_str("[]", &mut state).is_ok());
     }
 
     #[test]
     fn run_nonempty_loop() {
-        let mut state = BfState::new();
-        assert!(run("++[>+<-]", &mut state).is_ok());
+        let mut state = BfState::<u8>::new();
+        assert!(run_str("++[>+<-]", &mut state).is_ok());
         assert_eq!(state.memory[0], 0);
         assert_eq!(state.memory[1], 2);
     }
 
     #[test]
     fn run_loop_with_overflow() {
-        let mut state = BfState::new();
-        assert!(run("-[->+<]", &mut state).is_ok());
+        let mut state = BfState::<u8>::new();
+        assert!(run_str("-[->+<]", &mut state).is_ok());
         assert_eq!(state.memory[0], 0);
         assert_eq!(state.memory[1], 255);
 
-        state = BfState::new();
-        assert!(run("[+]", &mut state).is_ok());
-        assert!(run("+[+>+<]", &mut state).is_ok());
+        state = BfState::<u8>::new();
+        assert!(run_str("[+]", &mut state).is_ok());
+        assert!(run_str("+[+>+<]", &mut state).is_ok());
         assert_eq!(state.memory[0], 0);
         assert_eq!(state.memory[1], 255);
     }
 
     #[test]
     fn run_noop_loop() {
-        assert!(run("[<]", &mut BfState::new()).is_ok());
+        assert!(run_str("[<]", &mut BfState::<u8>::new()).is_ok());
+    }
+
+    #[test]
+    fn compile_folds_runs_of_identical_ops() {
+        let instrs = compile("+++--><<<").unwrap();
+        assert_eq!(instrs.len(), 4);
+    }
+
+    #[test]
+    fn compile_folded_add_wraps_like_single_steps() {
+        let mut state = BfState::<u8>::new();
+        assert!(run_str(&"+".repeat(257), &mut state).is_ok());
+        assert_eq!(state.curr(), 1);
+    }
+
+    #[test]
+    fn compile_jump_table_handles_nested_loops() {
+        let mut state = BfState::<u8>::new();
+        assert!(run_str("++[->++[->++<]<]", &mut state).is_ok());
+        assert_eq!(state.memory[0], 0);
+        assert_eq!(state.memory[1], 0);
+        assert_eq!(state.memory[2], 8);
     }
 
     #[test]
     fn run_nested_loops() {
-        let mut state = BfState::new();
-        assert!(run("-[->+<]", &mut state).is_ok());
+        let mut state = BfState::<u8>::new();
+        assert!(run_str("-[->+<]", &mut state).is_ok());
         assert_eq!(state.memory[0], 0);
         assert_eq!(state.memory[1], 255);
     }
 
     #[test]
     fn run_fails_on_mismatched_parens() {
-        let mut state = BfState::new();
-        let mut result = run("[]]", &mut state);
+        let mut state = BfState::<u8>::new();
+        let mut result = run_str("[]]", &mut state);
         assert!(result.is_err());
         assert_eq!(result.err(), Some(BfError::MismatchedBraces));
 
-        result = run("[[]", &mut state);
+        result = run_str("[[]", &mut state);
         assert!(result.is_err());
         assert_eq!(result.err(), Some(BfError::MismatchedBraces));
 
-        result = run("]", &mut state);
+        result = run_str("]", &mut state);
         assert!(result.is_err());
         assert_eq!(result.err(), Some(BfError::MismatchedBraces));
     }
 
     #[test]
     fn run_nontrivial_empty_loops() {
-        assert!(run("[[[]]]", &mut BfState::new()).is_ok());
-        assert!(run("[][][]", &mut BfState::new()).is_ok());
+        assert!(run_str("[[[]]]", &mut BfState::<u8>::new()).is_ok());
+        assert!(run_str("[][][]", &mut BfState::<u8>::new()).is_ok());
+    }
+
+    #[test]
+    fn run_captures_output() {
+        let mut state = BfState::<u8>::new();
+        let mut input = Cursor::new(Vec::new());
+        let mut output = Vec::new();
+        // '+' 65 times then '.' prints 'A'
+        let program = "+".repeat(65) + ".";
+        assert!(run(&program, &mut state, &mut input, &mut output).is_ok());
+        assert_eq!(output, vec![b'A']);
+    }
+
+    #[test]
+    fn run_reads_input() {
+        let mut state = BfState::<u8>::new();
+        let mut input = Cursor::new(vec![b'A']);
+        let mut output = Vec::new();
+        assert!(run(",.", &mut state, &mut input, &mut output).is_ok());
+        assert_eq!(output, vec![b'A']);
+    }
+
+    #[test]
+    fn run_read_at_eof_sets_cell_to_zero() {
+        let mut state = BfState::<u8>::new();
+        state.set_curr(42);
+        let mut input = Cursor::new(Vec::new());
+        let mut output = Vec::new();
+        assert!(run(",", &mut state, &mut input, &mut output).is_ok());
+        assert_eq!(state.curr(), 0);
+    }
+
+    #[test]
+    fn run_u16_cells_allow_wider_overflow() {
+        let mut state = BfState::<u16>::new();
+        let mut input = Cursor::new(Vec::new());
+        let mut output = Vec::new();
+        assert!(run(&"+".repeat(300), &mut state, &mut input, &mut output).is_ok());
+        assert_eq!(state.curr(), 300);
+    }
+
+    #[test]
+    fn run_u16_cells_write_little_endian_bytes() {
+        let mut state = BfState::<u16>::with_endian(Endian::Little);
+        state.set_curr(0x0102);
+        let mut input = Cursor::new(Vec::new());
+        let mut output = Vec::new();
+        assert!(run(".", &mut state, &mut input, &mut output).is_ok());
+        assert_eq!(output, vec![0x02, 0x01]);
+    }
+
+    #[test]
+    fn run_u16_cells_write_big_endian_bytes() {
+        let mut state = BfState::<u16>::with_endian(Endian::Big);
+        state.set_curr(0x0102);
+        let mut input = Cursor::new(Vec::new());
+        let mut output = Vec::new();
+        assert!(run(".", &mut state, &mut input, &mut output).is_ok());
+        assert_eq!(output, vec![0x01, 0x02]);
+    }
+
+    #[test]
+    fn run_u16_cells_read_respects_endianness() {
+        let mut state = BfState::<u16>::with_endian(Endian::Big);
+        let mut input = Cursor::new(vec![0x01, 0x02]);
+        let mut output = Vec::new();
+        assert!(run(",", &mut state, &mut input, &mut output).is_ok());
+        assert_eq!(state.curr(), 0x0102);
+    }
+
+    #[test]
+    fn cell_byte_widths_match_their_integer_size() {
+        assert_eq!(u8::byte_width(), 1);
+        assert_eq!(u16::byte_width(), 2);
+        assert_eq!(u32::byte_width(), 4);
+    }
+
+    #[test]
+    fn run_u32_cells_preserve_overflow_semantics() {
+        let mut state = BfState::<u32>::new();
+        state.set_curr(u32::MAX);
+        let mut input = Cursor::new(Vec::new());
+        let mut output = Vec::new();
+        assert!(run("+", &mut state, &mut input, &mut output).is_ok());
+        assert_eq!(state.curr(), 0);
     }
 }